@@ -49,12 +49,47 @@ pub struct AomEncoderConfig {
   pub height: u32,
   pub quality: Quality,
   pub keyframe_interval: Option<usize>,
+  // Number of temporal layers for SVC (1 disables SVC).
+  pub num_temporal_layers: u8,
+  // Encode/decode at 10-bit instead of the default 8-bit. Keeps the default
+  // fast path unchanged when left false.
+  pub high_bit_depth: bool,
+  // Opt in to per-frame PSNR reporting via `AomEncoder::psnr`.
+  pub enable_psnr: bool,
+}
+
+impl Default for AomEncoderConfig {
+  fn default() -> Self {
+    Self {
+      width: 0,
+      height: 0,
+      quality: Quality::Balanced,
+      keyframe_interval: None,
+      num_temporal_layers: 1,
+      high_bit_depth: false,
+      enable_psnr: false,
+    }
+  }
 }
 
 pub struct AomEncoder {
   ctx: aom_codec_ctx_t,
   width: usize,
   height: usize,
+  // The width/height the context was initialized with. `set_resolution`
+  // accepts any size up to this, never past it, since libaom can't grow a
+  // context beyond its initial frame size.
+  max_width: usize,
+  max_height: usize,
+  num_temporal_layers: u8,
+  // Index into the repeating temporal-layer pattern, advanced every encode().
+  svc_frame_index: u32,
+  // Set by `force_keyframe()`, consumed by the next `encode()` call.
+  force_keyframe: bool,
+  high_bit_depth: bool,
+  // Overall PSNR (in dB) of the most recently encoded frame, if
+  // `AomEncoderConfig::enable_psnr` is set.
+  last_psnr: Option<f64>,
 }
 
 // https://webrtc.googlesource.com/src/+/refs/heads/main/modules/video_coding/codecs/av1/libaom_av1_encoder.cc
@@ -85,6 +120,28 @@ mod webrtc {
     }
   }
 
+  // Pick a thread count from the frame resolution, mirroring Chromium's
+  // libaom AV1 encoder heuristic, clamped to the number of logical cores and
+  // to the configured upper bound so small regions don't waste cores/context
+  // switches while large ones get enough tiles.
+  pub fn get_num_threads(width: u32, _height: u32) -> u32 {
+    let desired = if width < 640 {
+      1
+    } else if width < 1280 {
+      2
+    } else if width < 2560 {
+      4
+    } else if width < 3840 {
+      8
+    } else {
+      16
+    };
+    let logical_cores = std::thread::available_parallelism()
+      .map(|n| n.get() as u32)
+      .unwrap_or(1);
+    desired.min(logical_cores).min(codec_thread_num() as u32)
+  }
+
   fn get_super_block_size(
     width: u32,
     height: u32,
@@ -109,10 +166,20 @@ mod webrtc {
     // Overwrite default config with input encoder settings & RTC-relevant values.
     c.g_w = cfg.width;
     c.g_h = cfg.height;
-    c.g_threads = codec_thread_num() as _;
+    c.g_threads = get_num_threads(cfg.width, cfg.height);
     c.g_timebase.num = 1;
     c.g_timebase.den = kRtpTicksPerSecond;
-    c.g_input_bit_depth = kBitDepth;
+    if cfg.high_bit_depth {
+      c.g_bit_depth = aom_bit_depth::AOM_BITS_10;
+      c.g_input_bit_depth = 10;
+      // Profile 0 already covers 8-bit/10-bit 4:2:0, which is all this
+      // high-bit-depth path adds; profile 2 is only needed for 12-bit or
+      // 4:2:2/4:4:4 input, which we don't produce here.
+      c.g_profile = 0;
+    } else {
+      c.g_bit_depth = aom_bit_depth::AOM_BITS_8;
+      c.g_input_bit_depth = kBitDepth;
+    }
     if let Some(keyframe_interval) = cfg.keyframe_interval {
       c.kf_min_dist = 0;
       c.kf_max_dist = keyframe_interval as _;
@@ -149,6 +216,126 @@ mod webrtc {
     Ok(c)
   }
 
+  // Configure a dyadic temporal-layer SVC structure so the transport can drop
+  // the top temporal layer on congestion without breaking decode of the rest.
+  // See: https://webrtc.googlesource.com/src/+/refs/heads/main/modules/video_coding/codecs/av1/libaom_av1_encoder.cc
+  pub fn set_svc_params(
+    ctx: *mut aom_codec_ctx_t,
+    num_temporal_layers: u8,
+    target_bitrate: u32,
+    q_min: u32,
+    q_max: u32,
+  ) -> ResultType<()> {
+    use aome_enc_control_id::*;
+    if num_temporal_layers <= 1 {
+      return Ok(());
+    }
+    if num_temporal_layers > 2 {
+      // Only the dyadic 2-layer pattern below is implemented; higher layer
+      // counts would leave framerate_factor/layer_target_bitrate/quantizers
+      // zero-filled for layers 2+, which libaom will reject or mis-encode.
+      return Err(anyhow!(
+        "unsupported num_temporal_layers: {} (only 1 or 2 are supported)",
+        num_temporal_layers
+      ));
+    }
+    let mut svc_params: aom_svc_params_t = unsafe { std::mem::zeroed() };
+    svc_params.number_spatial_layers = 1;
+    svc_params.number_temporal_layers = num_temporal_layers as i32;
+    // Dyadic 2-layer pattern: the base layer (T0) is coded at half the frame
+    // rate of the enhancement layer (T1).
+    svc_params.framerate_factor[0] = 2;
+    svc_params.framerate_factor[1] = 1;
+    // The base layer is also referenced by the enhancement layer, so give it
+    // the larger share: 60% of the total. Layer targets are cumulative, so
+    // T1's target is the full rate and T1 itself adds the remaining 40%.
+    svc_params.layer_target_bitrate[0] = (target_bitrate * 6 / 10) as i32;
+    svc_params.layer_target_bitrate[1] = target_bitrate as i32;
+    svc_params.min_quantizers[0] = q_min as i32;
+    svc_params.min_quantizers[1] = q_min as i32;
+    svc_params.max_quantizers[0] = q_max as i32;
+    svc_params.max_quantizers[1] = q_max as i32;
+    call_aom_allow_err!(aom_codec_control(
+      ctx,
+      AV1E_SET_SVC_PARAMS as i32,
+      &mut svc_params as *mut aom_svc_params_t
+    ));
+    Ok(())
+  }
+
+  // Tag the current frame with its temporal layer id and restrict which
+  // previously-coded layers it may reference, so the enhancement layer never
+  // becomes a reference for the base layer.
+  pub fn set_svc_layer_id(
+    ctx: *mut aom_codec_ctx_t,
+    temporal_layer_id: u32,
+  ) -> ResultType<()> {
+    use aome_enc_control_id::*;
+    let mut layer_id: aom_svc_layer_id_t = unsafe { std::mem::zeroed() };
+    layer_id.spatial_layer_id = 0;
+    layer_id.temporal_layer_id = temporal_layer_id as i32;
+    call_aom_allow_err!(aom_codec_control(
+      ctx,
+      AV1E_SET_SVC_LAYER_ID as i32,
+      &mut layer_id as *mut aom_svc_layer_id_t
+    ));
+
+    let mut ref_cfg: aom_svc_ref_frame_config_t = unsafe { std::mem::zeroed() };
+    if temporal_layer_id == 0 {
+      // Base layer: references and refreshes its own buffer slot, so T0
+      // frames still predict from the previous T0 frame.
+      ref_cfg.reference[0] = 1;
+      ref_cfg.ref_idx[0] = 0;
+      ref_cfg.refresh[0] = 1;
+    } else {
+      // Enhancement layer: references the base layer's slot but never
+      // refreshes it, so dropping this layer can't corrupt the base.
+      ref_cfg.reference[0] = 1;
+      ref_cfg.ref_idx[0] = 0;
+      ref_cfg.refresh[1] = 1;
+    }
+    call_aom_allow_err!(aom_codec_control(
+      ctx,
+      AV1E_SET_SVC_REF_FRAME_CONFIG as i32,
+      &mut ref_cfg as *mut aom_svc_ref_frame_config_t
+    ));
+    Ok(())
+  }
+
+  // The subset of resolution/thread-dependent controls. Split out of
+  // `set_controls` so `AomEncoder::set_resolution` can re-derive them for the
+  // new frame size without resending every other (resolution-independent)
+  // control.
+  pub fn set_resolution_controls(
+    ctx: *mut aom_codec_ctx_t,
+    cfg: &aom_codec_enc_cfg,
+  ) -> ResultType<()> {
+    use aome_enc_control_id::*;
+    macro_rules! call_ctl {
+      ($ctx:expr, $av1e:expr, $arg:expr) => {{
+        call_aom_allow_err!(aom_codec_control($ctx, $av1e as i32, $arg));
+      }};
+    }
+
+    call_ctl!(ctx, AOME_SET_CPUUSED, get_cpu_speed(cfg.g_w, cfg.g_h));
+    let tile_set = if cfg.g_threads == 4
+      && cfg.g_w == 640
+      && (cfg.g_h == 360 || cfg.g_h == 480)
+    {
+      AV1E_SET_TILE_ROWS
+    } else {
+      AV1E_SET_TILE_COLUMNS
+    };
+    // Failed on android
+    call_ctl!(ctx, tile_set, (cfg.g_threads as f64 * 1.0f64).log2().ceil());
+    call_ctl!(
+      ctx,
+      AV1E_SET_SUPERBLOCK_SIZE,
+      get_super_block_size(cfg.g_w, cfg.g_h, cfg.g_threads)
+    );
+    Ok(())
+  }
+
   pub fn set_controls(
     ctx: *mut aom_codec_ctx_t,
     cfg: &aom_codec_enc_cfg,
@@ -161,7 +348,7 @@ mod webrtc {
       }};
     }
 
-    call_ctl!(ctx, AOME_SET_CPUUSED, get_cpu_speed(cfg.g_w, cfg.g_h));
+    set_resolution_controls(ctx, cfg)?;
     call_ctl!(ctx, AV1E_SET_ENABLE_CDEF, 1);
     call_ctl!(ctx, AV1E_SET_ENABLE_TPL_MODEL, 0);
     call_ctl!(ctx, AV1E_SET_DELTAQ_MODE, 0);
@@ -174,27 +361,12 @@ mod webrtc {
     // kScreensharing
     call_ctl!(ctx, AV1E_SET_TUNE_CONTENT, AOM_CONTENT_SCREEN);
     call_ctl!(ctx, AV1E_SET_ENABLE_PALETTE, 1);
-    let tile_set = if cfg.g_threads == 4
-      && cfg.g_w == 640
-      && (cfg.g_h == 360 || cfg.g_h == 480)
-    {
-      AV1E_SET_TILE_ROWS
-    } else {
-      AV1E_SET_TILE_COLUMNS
-    };
-    // Failed on android
-    call_ctl!(ctx, tile_set, (cfg.g_threads as f64 * 1.0f64).log2().ceil());
     call_ctl!(ctx, AV1E_SET_ROW_MT, 1);
     call_ctl!(ctx, AV1E_SET_ENABLE_OBMC, 0);
     call_ctl!(ctx, AV1E_SET_NOISE_SENSITIVITY, 0);
     call_ctl!(ctx, AV1E_SET_ENABLE_WARPED_MOTION, 0);
     call_ctl!(ctx, AV1E_SET_ENABLE_GLOBAL_MOTION, 0);
     call_ctl!(ctx, AV1E_SET_ENABLE_REF_FRAME_MVS, 0);
-    call_ctl!(
-      ctx,
-      AV1E_SET_SUPERBLOCK_SIZE,
-      get_super_block_size(cfg.g_w, cfg.g_h, cfg.g_threads)
-    );
     call_ctl!(ctx, AV1E_SET_ENABLE_CFL_INTRA, 0);
     call_ctl!(ctx, AV1E_SET_ENABLE_SMOOTH_INTRA, 0);
     call_ctl!(ctx, AV1E_SET_ENABLE_ANGLE_DELTA, 0);
@@ -233,7 +405,13 @@ impl EncoderApi for AomEncoder {
 
         let mut ctx = Default::default();
         // Flag options: AOM_CODEC_USE_PSNR and AOM_CODEC_USE_HIGHBITDEPTH
-        let flags: aom_codec_flags_t = 0;
+        let mut flags: aom_codec_flags_t = 0;
+        if config.high_bit_depth {
+          flags |= AOM_CODEC_USE_HIGHBITDEPTH;
+        }
+        if config.enable_psnr {
+          flags |= AOM_CODEC_USE_PSNR;
+        }
         call_aom!(aom_codec_enc_init_ver(
           &mut ctx,
           i,
@@ -242,10 +420,24 @@ impl EncoderApi for AomEncoder {
           AOM_ENCODER_ABI_VERSION as _
         ));
         webrtc::set_controls(&mut ctx, &c)?;
+        webrtc::set_svc_params(
+          &mut ctx,
+          config.num_temporal_layers,
+          c.rc_target_bitrate,
+          c.rc_min_quantizer,
+          c.rc_max_quantizer,
+        )?;
         Ok(Self {
           ctx,
           width: config.width as _,
           height: config.height as _,
+          max_width: config.width as _,
+          max_height: config.height as _,
+          num_temporal_layers: config.num_temporal_layers,
+          svc_frame_index: 0,
+          force_keyframe: false,
+          high_bit_depth: config.high_bit_depth,
+          last_psnr: None,
         })
       }
       _ => Err(anyhow!("encoder type mismatch")),
@@ -297,37 +489,104 @@ impl EncoderApi for AomEncoder {
 }
 
 impl AomEncoder {
+  /// Force the next encoded frame to be a keyframe, e.g. in response to a
+  /// NACK/PLI-style request from the remote decoder after packet loss.
+  #[inline]
+  pub fn force_keyframe(&mut self) {
+    self.force_keyframe = true;
+  }
+
+  /// Overall PSNR (in dB) of the most recently encoded frame. `None` unless
+  /// `AomEncoderConfig::enable_psnr` was set.
+  #[inline]
+  pub fn psnr(&self) -> Option<f64> {
+    self.last_psnr
+  }
+
+  /// Change the encoded resolution without recreating the encoder, so
+  /// reference state survives a window/monitor resize. `width`/`height` must
+  /// not exceed the dimensions the encoder was initialized with; libaom has
+  /// no way to grow a context past its initial frame size. Forces a keyframe
+  /// on the first frame encoded after the change.
+  pub fn set_resolution(&mut self, width: u32, height: u32) -> ResultType<()> {
+    if width as usize > self.max_width || height as usize > self.max_height {
+      return Err(anyhow!(
+        "resolution {}x{} exceeds the {}x{} this encoder was initialized with",
+        width,
+        height,
+        self.max_width,
+        self.max_height
+      ));
+    }
+    let mut c = unsafe { *self.ctx.config.enc.to_owned() };
+    c.g_w = width;
+    c.g_h = height;
+    c.g_threads = webrtc::get_num_threads(width, height);
+    call_aom!(aom_codec_enc_config_set(&mut self.ctx, &c));
+    webrtc::set_resolution_controls(&mut self.ctx, &c)?;
+    self.width = width as _;
+    self.height = height as _;
+    self.force_keyframe = true;
+    Ok(())
+  }
+
   pub fn encode(
     &mut self,
     pts: i64,
     data: &[u8],
     stride_align: usize,
   ) -> Result<EncodeFrames> {
-    if 2 * data.len() < 3 * self.width * self.height {
+    // I420 (4:2:0) is 1.5 bytes/pixel at 8 bits/sample; high-bit-depth packs
+    // every sample into 2 bytes, doubling that minimum.
+    let bytes_per_sample = if self.high_bit_depth { 2 } else { 1 };
+    if 2 * data.len() < 3 * self.width * self.height * bytes_per_sample {
       return Err(Error::FailedCall("len not enough".to_string()));
     }
 
+    let fmt = if self.high_bit_depth {
+      aom_img_fmt::AOM_IMG_FMT_I42016
+    } else {
+      aom_img_fmt::AOM_IMG_FMT_I420
+    };
     let mut image = Default::default();
     call_aom_ptr!(aom_img_wrap(
       &mut image,
-      aom_img_fmt::AOM_IMG_FMT_I420,
+      fmt,
       self.width as _,
       self.height as _,
       stride_align as _,
       data.as_ptr() as _,
     ));
 
+    let temporal_id = if self.num_temporal_layers > 1 {
+      // Repeating dyadic pattern: 0, 1, 0, 1, ...
+      let temporal_id = self.svc_frame_index % self.num_temporal_layers as u32;
+      webrtc::set_svc_layer_id(&mut self.ctx, temporal_id)?;
+      self.svc_frame_index = self.svc_frame_index.wrapping_add(1);
+      temporal_id
+    } else {
+      0
+    };
+
+    let flags = if self.force_keyframe {
+      self.force_keyframe = false;
+      AOM_EFLAG_FORCE_KF
+    } else {
+      0
+    };
     call_aom!(aom_codec_encode(
       &mut self.ctx,
       &image,
       pts as _,
       1, // Duration
-      0, // Flags
+      flags as _,
     ));
 
     Ok(EncodeFrames {
       ctx: &mut self.ctx,
       iter: ptr::null(),
+      temporal_id,
+      psnr: &mut self.last_psnr,
     })
   }
 
@@ -399,6 +658,11 @@ impl Drop for AomEncoder {
 pub struct EncodeFrames<'a> {
   ctx: &'a mut aom_codec_ctx_t,
   iter: aom_codec_iter_t,
+  // Temporal layer id that was set on the encoder for this frame, so it can
+  // be attached to the resulting packet(s) for the transport to act on.
+  temporal_id: u32,
+  // Where to stash the overall PSNR reported for this frame, if any.
+  psnr: &'a mut Option<f64>,
 }
 
 impl<'a> Iterator for EncodeFrames<'a> {
@@ -415,7 +679,11 @@ impl<'a> Iterator for EncodeFrames<'a> {
             data: slice::from_raw_parts(f.buf as _, f.sz as _),
             key: (f.flags & AOM_FRAME_IS_KEY) != 0,
             pts: f.pts,
+            temporal_id: self.temporal_id,
           });
+        } else if (*pkt).kind == aom_codec_cx_pkt_kind::AOM_CODEC_PSNR_PKT {
+          let psnr = &(*pkt).data.psnr;
+          *self.psnr = Some(psnr.psnr[0]);
         } else {
           // Ignore the packet.
         }
@@ -430,13 +698,20 @@ pub struct AomDecoder {
 
 impl AomDecoder {
   pub fn new() -> Result<Self> {
+    Self::new_ex(false)
+  }
+
+  /// `high_bit_depth` must match the encoder's `AomEncoderConfig::high_bit_depth`;
+  /// when set, decoded frames come back as native 16-bit-per-sample planes
+  /// instead of being packed down to 8-bit.
+  pub fn new_ex(high_bit_depth: bool) -> Result<Self> {
     let i = call_aom_ptr!(aom_codec_av1_dx());
     let mut ctx = Default::default();
     let cfg = aom_codec_dec_cfg_t {
       threads: codec_thread_num() as _,
       w: 0,
       h: 0,
-      allow_lowbitdepth: 1,
+      allow_lowbitdepth: if high_bit_depth { 0 } else { 1 },
     };
     call_aom!(aom_codec_dec_init_ver(
       &mut ctx,
@@ -548,6 +823,17 @@ impl GoogleImage for Image {
   fn planes(&self) -> Vec<*mut u8> {
     self.inner().planes.iter().map(|p| *p as *mut u8).collect()
   }
+
+  /// 2 for the 10-bit `AOM_IMG_FMT_I42016` path, 1 for the default 8-bit
+  /// path.
+  #[inline]
+  fn bytes_per_sample(&self) -> usize {
+    if self.inner().fmt as u32 & AOM_IMG_FMT_HIGHBITDEPTH != 0 {
+      2
+    } else {
+      1
+    }
+  }
 }
 
 impl Drop for Image {
@@ -0,0 +1,20 @@
+pub mod aom;
+
+/// Common interface over the image types returned by the codecs in this
+/// module, so YUV conversion can operate generically regardless of which
+/// codec produced the frame.
+pub trait GoogleImage {
+  fn width(&self) -> usize;
+  fn height(&self) -> usize;
+  fn stride(&self) -> Vec<i32>;
+  fn planes(&self) -> Vec<*mut u8>;
+
+  /// Bytes used per sample in each plane. Codecs that only ever produce
+  /// 8-bit output can rely on the default; high-bit-depth paths must
+  /// override this so downstream YUV conversion reads planes with the
+  /// right strides.
+  #[inline]
+  fn bytes_per_sample(&self) -> usize {
+    1
+  }
+}